@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+// Write `contents` to `path` atomically: the data is written to a sibling temp
+// file on the same filesystem, flushed and synced to disk, and only then
+// renamed over `path`. This means a crash or power loss mid-write can never
+// leave `path` holding a truncated or corrupt file.
+//
+// On Unix the temp file is chmod'd to 0600 before the rename, so secret-bearing
+// contents are never briefly world-readable, and the containing directory is
+// synced after the rename so the rename itself is durable.
+pub fn atomic_write(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| Box::<dyn Error>::from("Path has no parent directory"))?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Box::<dyn Error>::from("Path has no file name"))?;
+
+    let mut tmp_path = dir.to_path_buf();
+    tmp_path.push(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    {
+        let mut file = File::create(&tmp_path)?;
+
+        #[cfg(unix)]
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+        file.write_all(contents)?;
+        file.flush()?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&tmp_path, path)?;
+
+    // Sync the containing directory so the rename itself is durable.
+    #[cfg(unix)]
+    {
+        let dir_file = File::open(dir)?;
+        dir_file.sync_all()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_and_overwrites_the_target_file() {
+        let dir = std::env::temp_dir().join(format!("mosaic-id-atomic-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("target.txt");
+
+        atomic_write(&path, b"first").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+
+        atomic_write(&path, b"second, and longer").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second, and longer");
+
+        // No leftover temp file.
+        let leftovers: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name())
+            .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty(), "left a temp file behind: {leftovers:?}");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sets_restrictive_permissions_on_unix() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("mosaic-id-atomic-perm-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secret.txt");
+
+        atomic_write(&path, b"shh").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
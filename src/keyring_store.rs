@@ -0,0 +1,35 @@
+use std::error::Error;
+
+use keyring::Entry;
+use mosaic_core::PublicKey;
+
+// All Mosaic identities' stored passwords live under this single keyring service,
+// one entry per identity, keyed by its public key.
+const SERVICE: &str = "mosaic-id";
+
+fn entry_for(public_key: &PublicKey) -> Result<Entry, Box<dyn Error>> {
+    Ok(Entry::new(SERVICE, &public_key.to_string())?)
+}
+
+// Store the password that decrypts this identity's master key in the platform
+// secret store (Secret Service / macOS Keychain / Windows Credential Manager).
+pub fn remember_password(public_key: &PublicKey, password: &str) -> Result<(), Box<dyn Error>> {
+    entry_for(public_key)?.set_password(password)?;
+    Ok(())
+}
+
+// Look up a previously remembered password for this identity, if any. Absence
+// or any keyring error is treated as "no password available" rather than a
+// hard failure, since this is only ever used for an optional silent unlock.
+pub fn recall_password(public_key: &PublicKey) -> Option<String> {
+    entry_for(public_key).ok()?.get_password().ok()
+}
+
+// Delete a previously remembered password. Deleting an entry that was never
+// stored is not an error.
+pub fn forget_password(public_key: &PublicKey) -> Result<(), Box<dyn Error>> {
+    match entry_for(public_key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
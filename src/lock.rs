@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+
+use fs2::FileExt;
+
+use crate::dir::mosaic_dir;
+
+// Holds a non-blocking exclusive advisory lock on `.lock` inside the mosaic
+// data directory for as long as it is alive. The lock is released (and the
+// other process unblocked) as soon as this is dropped.
+pub struct DirLock {
+    file: File,
+}
+
+impl DirLock {
+    // Acquire the lock, or return an error describing that another instance
+    // already holds it. Callers should report the error and exit cleanly
+    // rather than proceeding, since two instances operating on the same data
+    // directory can silently clobber each other's changes.
+    pub fn acquire() -> Result<DirLock, Box<dyn Error>> {
+        let mut path = mosaic_dir()?;
+        path.push(".lock");
+
+        let file = OpenOptions::new().create(true).write(true).open(&path)?;
+
+        file.try_lock_exclusive().map_err(|_| {
+            Box::<dyn Error>::from(
+                "Another mosaic-id process is already running against this data directory; exiting.",
+            )
+        })?;
+
+        Ok(DirLock { file })
+    }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
@@ -1,19 +1,169 @@
+mod atomic;
+mod dir;
+mod keyring_store;
+mod lock;
+mod paths;
+mod secret;
+
 use std::error::Error;
 use std::fs;
 use std::io::{self, BufRead, Write};
-use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use serde::{Deserialize, Serialize};
 
 use mosaic_core::{EncryptedSecretKey, PublicKey, SecretKey, UserBootstrap};
+use secret::ZeroizingSecretKey;
+
+// The character set used by Mosaic's printable encoding (see `PublicKey::from_printable`
+// and its `Display` impl). A requested prefix/suffix can only ever match if every
+// character it contains is drawn from this alphabet.
+const PRINTABLE_ALPHABET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// Every printable public key starts with this fixed human-readable part and
+// bech32 separator (e.g. "mopub1...") before the variable data that a vanity
+// search can actually influence. Matching a requested prefix against the raw
+// printable string would compare it against this constant segment, where it
+// could never match; strip it first.
+const PUBLIC_KEY_HRP: &str = "mopub1";
+
+// The part of a printable public key that a vanity prefix/suffix can match:
+// everything after the fixed human-readable part and separator.
+fn vanity_data_portion(printable: &str) -> &str {
+    printable.strip_prefix(PUBLIC_KEY_HRP).unwrap_or(printable)
+}
+
+// Roughly how many attempts we expect to need before a random public key matches
+// the given prefix/suffix, treating each character as independently narrowing the
+// search space by the alphabet size.
+fn expected_vanity_attempts(prefix: &str, suffix: &str) -> u64 {
+    let matched_chars = prefix.chars().count() + suffix.chars().count();
+    (PRINTABLE_ALPHABET.len() as u64).saturating_pow(matched_chars as u32)
+}
+
+// Reject patterns up front that could never match: either because they
+// contain a character outside Mosaic's printable alphabet, or because they
+// are longer than the data portion of a printable public key and so could
+// never fit regardless of how long the search runs. Callers must pass
+// already-lowercased `prefix`/`suffix`, since the data portion they are
+// matched against is always lowercase.
+fn validate_vanity_pattern(prefix: &str, suffix: &str, data_len: usize) -> Result<(), String> {
+    for c in prefix.chars().chain(suffix.chars()) {
+        if !PRINTABLE_ALPHABET.contains(c) {
+            return Err(format!(
+                "'{c}' does not appear in Mosaic's printable alphabet ({PRINTABLE_ALPHABET}); \
+                 this pattern can never match."
+            ));
+        }
+    }
+
+    let matched_chars = prefix.chars().count() + suffix.chars().count();
+    if matched_chars > data_len {
+        return Err(format!(
+            "Prefix and suffix together are {matched_chars} characters, but the data portion \
+             of a printable public key is only {data_len} characters; this pattern can never \
+             match."
+        ));
+    }
+
+    Ok(())
+}
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UserProfile; // define in mosaic-core
 
+impl UserProfile {
+    // mosaic-core does not define a binary encoding for profiles yet, so there
+    // is nothing honest this can persist as `profile.morec`. Error loudly
+    // instead of silently writing/reading empty records. `NewProfile` does not
+    // populate this field, so `to_bytes` is not reached today; `from_bytes` is
+    // called at startup whenever `profile.morec` exists, and startup then fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err("UserProfile has no binary encoding yet (mosaic-core does not define one)".into())
+    }
+
+    pub fn from_bytes(_bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Err("UserProfile has no binary encoding yet (mosaic-core does not define one)".into())
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct KeyCertificate; // define in mosaic-core
 
+impl KeyCertificate {
+    // Same situation as `UserProfile::to_bytes`: no real encoding exists yet,
+    // so refuse to fabricate one. `NewKeySchedule` does not populate this
+    // field, so `to_bytes` is not reached today; `from_bytes` is called at
+    // startup for each record of an existing non-empty `key_schedule.morec`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err("KeyCertificate has no binary encoding yet (mosaic-core does not define one)".into())
+    }
+
+    pub fn from_bytes(_bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Err("KeyCertificate has no binary encoding yet (mosaic-core does not define one)".into())
+    }
+}
+
+// BIP39 entropy is only defined for 16/20/24/28/32-byte inputs (12/15/18/21/24
+// words respectively). Mosaic's `SecretKey` is expected to be the 32-byte
+// case, matching the longest (24-word) phrase; check explicitly rather than
+// letting a mismatch surface as an opaque `bip39` error the first time
+// someone tries to back up their key.
+fn secret_key_to_mnemonic(secret_key: &SecretKey) -> Result<bip39::Mnemonic, Box<dyn Error>> {
+    let entropy = secret_key.to_bytes();
+    if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+        return Err(format!(
+            "SecretKey is {} bytes, which is not a valid BIP39 entropy length \
+             (16/20/24/28/32); recovery phrases are not supported for this key size.",
+            entropy.len()
+        )
+        .into());
+    }
+    Ok(bip39::Mnemonic::from_entropy(&entropy)?)
+}
+
+// Encode a key schedule as a simple length-prefixed sequence of records, since
+// it is the only field that is a list rather than a single value.
+fn encode_key_schedule(certs: &[KeyCertificate]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(certs.len() as u32).to_le_bytes());
+    for cert in certs {
+        let bytes = cert.to_bytes()?;
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+    Ok(out)
+}
+
+fn decode_key_schedule(bytes: &[u8]) -> Result<Vec<KeyCertificate>, Box<dyn Error>> {
+    let mut certs = Vec::new();
+    let mut offset = 0;
+
+    let count_bytes = bytes
+        .get(offset..offset + 4)
+        .ok_or("key schedule truncated: missing count")?;
+    let count = u32::from_le_bytes(count_bytes.try_into().unwrap());
+    offset += 4;
+
+    for _ in 0..count {
+        let len_bytes = bytes
+            .get(offset..offset + 4)
+            .ok_or("key schedule truncated: missing record length")?;
+        let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+        offset += 4;
+
+        let record = bytes
+            .get(offset..offset + len)
+            .ok_or("key schedule truncated: record shorter than its declared length")?;
+        certs.push(KeyCertificate::from_bytes(record)?);
+        offset += len;
+    }
+
+    Ok(certs)
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Data {
     pub encrypted_master_key: Option<EncryptedSecretKey>,
@@ -24,78 +174,96 @@ pub struct Data {
 
 pub struct Params {
     data: Data,
-    config_file: PathBuf,
-    secret_key: Option<SecretKey>,
+    paths: paths::Paths,
+    secret_key: Option<ZeroizingSecretKey>,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let config_file = data_path()?;
-
-    let data: Data = if config_file.exists() {
-        let contents = fs::read(&config_file)?;
-        serde_json::from_slice(&contents)?
-    } else {
-        Data::default()
+    let _lock = match lock::DirLock::acquire() {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(());
+        }
     };
 
-    eprintln!("Current Data: {}", serde_json::to_string_pretty(&data)?);
+    let paths = paths::paths()?;
 
-    let params = Params {
-        data,
-        config_file,
-        secret_key: None,
+    let encrypted_master_key = if paths.master_key.exists() {
+        Some(EncryptedSecretKey::from_bytes(&fs::read(
+            &paths.master_key,
+        )?)?)
+    } else {
+        None
     };
 
-    run_main_menu(params)?;
-
-    Ok(())
-}
-
-fn data_path() -> Result<PathBuf, Box<dyn Error>> {
-    let mut data_dir = normalize(
-        dirs::data_dir().ok_or(Box::<dyn Error>::from("Cannot determine data directory"))?,
-    );
+    let bootstrap = if paths.bootstrap.exists() {
+        Some(UserBootstrap::from_bytes(&fs::read(&paths.bootstrap)?)?)
+    } else {
+        None
+    };
 
-    // Add "mosaic" to the end
-    data_dir.push("mosaic");
+    let profile = if paths.profile.exists() {
+        Some(UserProfile::from_bytes(&fs::read(&paths.profile)?)?)
+    } else {
+        None
+    };
 
-    // normalize again in case mosaic existed and was a link
-    let data_dir = normalize(data_dir.as_path());
+    let key_schedule = if paths.key_schedule.exists() {
+        Some(decode_key_schedule(&fs::read(&paths.key_schedule)?)?)
+    } else {
+        None
+    };
 
-    // Create the directory if it does not already exist
-    fs::create_dir_all(&data_dir)?;
+    let data = Data {
+        encrypted_master_key,
+        bootstrap,
+        profile,
+        key_schedule,
+    };
 
-    let mut data_path = data_dir;
+    eprintln!("Current Data: {}", serde_json::to_string_pretty(&data)?);
 
-    data_path.push("mosaic.json");
+    // Try a silent unlock via a password remembered in the OS keyring, so the
+    // user is not forced to re-enter it on every launch. This is strictly
+    // opt-in: without a remembered password, nothing changes here.
+    let secret_key = data
+        .encrypted_master_key
+        .as_ref()
+        .and_then(|emk| {
+            let public_key = emk.public_key();
+            let password = keyring_store::recall_password(&public_key)?;
+            emk.to_secret_key(&password).ok()
+        })
+        .map(ZeroizingSecretKey::new);
 
-    Ok(data_path)
-}
+    let params = Params {
+        data,
+        paths,
+        secret_key,
+    };
 
-#[cfg(not(windows))]
-fn normalize<P: AsRef<Path>>(path: P) -> PathBuf {
-    fs::canonicalize(&path).unwrap_or(path.as_ref().to_path_buf())
-}
+    run_main_menu(params)?;
 
-#[cfg(windows)]
-fn normalize<P: AsRef<Path>>(path: P) -> PathBuf {
-    match path.as_ref().normalize() {
-        Ok(p) => p.into_path_buf(),
-        Err(_) => path.as_ref().to_path_buf(),
-    }
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MenuOption {
     NewMaster,
+    NewVanityMaster,
+    RestoreFromRecoveryPhrase,
     DecryptMaster,
     DestroyMaster,
+    ShowRecoveryPhrase,
     NewBootstrap,
     EditBootstrap,
     NewProfile,
     EditProfile,
     NewKeySchedule,
     EditKeySchedule,
+    RememberPassword,
+    ForgetPassword,
     SaveAndExit,
     ExitWithoutSaving,
 
@@ -113,14 +281,21 @@ impl MenuOption {
     pub fn prompt(&self) -> &'static str {
         match self {
             Self::NewMaster => "Generate a new Master Keypair",
+            Self::NewVanityMaster => {
+                "Generate a new vanity Master Keypair (matching a prefix/suffix)"
+            }
+            Self::RestoreFromRecoveryPhrase => "Restore Master Keypair from recovery phrase",
             Self::DecryptMaster => "Decrypt your Master Keypair (so we can operate with it)",
             Self::DestroyMaster => "DESTROY your Master Keypair (DANGER!)",
+            Self::ShowRecoveryPhrase => "Show recovery phrase (write it down and store it safely)",
             Self::NewBootstrap => "Generate a new empty Bootstrap",
             Self::EditBootstrap => "Edit Bootstrap",
             Self::NewProfile => "Generate a new empty Profile",
             Self::EditProfile => "Edit Profile",
             Self::NewKeySchedule => "Generate a new empty Key Schedule",
             Self::EditKeySchedule => "Edit Key Schedule",
+            Self::RememberPassword => "Remember master key password in OS keyring",
+            Self::ForgetPassword => "Forget password stored in OS keyring",
             Self::SaveAndExit => "Save and Quit",
             Self::ExitWithoutSaving => "Quit Without Saving",
 
@@ -157,9 +332,16 @@ pub fn main_options_from_params(params: &Params) -> Vec<MenuOption> {
         } else {
             options.push(MenuOption::DecryptMaster);
         }
+        options.push(MenuOption::RememberPassword);
+        options.push(MenuOption::ForgetPassword);
+        if params.secret_key.is_some() {
+            options.push(MenuOption::ShowRecoveryPhrase);
+        }
         options.push(MenuOption::DestroyMaster);
     } else {
         options.push(MenuOption::NewMaster);
+        options.push(MenuOption::NewVanityMaster);
+        options.push(MenuOption::RestoreFromRecoveryPhrase);
     }
 
     options.push(MenuOption::SaveAndExit);
@@ -168,6 +350,27 @@ pub fn main_options_from_params(params: &Params) -> Vec<MenuOption> {
     options
 }
 
+// Enables crossterm raw mode for as long as it is alive, and disables it
+// again on drop -- including on an early return or a panic unwind. Without
+// this, the terminal stays in cooked mode with ISIG enabled, so a Ctrl-C
+// generates a real SIGINT that kills the process before any Rust code
+// (including the interception in `run_menu_once` or `ZeroizingSecretKey`'s
+// `Drop`) ever runs.
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<RawModeGuard, Box<dyn Error>> {
+        crossterm::terminal::enable_raw_mode()?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
 pub fn run_main_menu(mut params: Params) -> Result<(), Box<dyn Error>> {
     loop {
         let options = main_options_from_params(&params);
@@ -194,17 +397,41 @@ pub fn run_menu_once(
     print!("{}> ", indent);
     stdout.flush()?;
 
+    // Raw mode is required for crossterm to hand us Ctrl-C as a plain key
+    // event instead of the terminal raising SIGINT itself; it is scoped
+    // tightly to this read loop (rather than the whole menu loop) because
+    // `execute` below falls back to cooked-mode `stdin().lines()` reads and
+    // `rpassword` prompts for its own sub-prompts, which need line buffering
+    // and echo restored.
+    let raw_mode = RawModeGuard::enable()?;
+
     // Handle one command from the menu
     loop {
-        if let Event::Key(key_event) = crossterm::event::read()?
-            && let KeyCode::Char(c) = key_event.code
-            && let Some(digit) = c.to_digit(10)
-        {
-            let index = digit as usize;
-            if index < options.len() {
-                println!();
-                let exit = execute(options[index], params)?;
-                return Ok(exit);
+        if let Event::Key(key_event) = crossterm::event::read()? {
+            // With raw mode active above, Ctrl-C arrives here as a plain key
+            // event instead of the terminal's default SIGINT, so we have to
+            // treat it as an immediate, safe abort ourselves: drop the secret
+            // key (zeroizing it) before exiting.
+            if key_event.code == KeyCode::Char('c')
+                && key_event.modifiers.contains(KeyModifiers::CONTROL)
+            {
+                println!("\n^C");
+                params.secret_key = None;
+                return Ok(true);
+            }
+
+            if let KeyCode::Char(c) = key_event.code
+                && let Some(digit) = c.to_digit(10)
+            {
+                let index = digit as usize;
+                if index < options.len() {
+                    // Restore cooked mode before `execute`, which reads its
+                    // own sub-prompts via line-buffered stdin.
+                    drop(raw_mode);
+                    println!();
+                    let exit = execute(options[index], params)?;
+                    return Ok(exit);
+                }
             }
         }
     }
@@ -218,6 +445,99 @@ pub fn execute(option: MenuOption, params: &mut Params) -> Result<bool, Box<dyn
         MenuOption::NewMaster => {
             let secret_key = SecretKey::generate();
             let public_key = secret_key.public();
+            let password =
+                rpassword::prompt_password("Enter new password to encrypt your master key: ")?;
+            println!("Encrypting...");
+            let encrypted_secret_key =
+                EncryptedSecretKey::from_secret_key(&secret_key, &password, 18);
+            params.data.encrypted_master_key = Some(encrypted_secret_key);
+            params.secret_key = Some(ZeroizingSecretKey::new(secret_key));
+            println!("Master Key generated.");
+            println!("Your Mosaic Identity is: {}", public_key);
+        }
+        MenuOption::NewVanityMaster => {
+            print!("Enter desired prefix (blank for none): ");
+            io::stdout().flush()?;
+            let stdin = io::stdin();
+            let prefix = stdin
+                .lock()
+                .lines()
+                .next()
+                .unwrap()?
+                .trim()
+                .to_ascii_lowercase();
+
+            print!("Enter desired suffix (blank for none): ");
+            io::stdout().flush()?;
+            let suffix = stdin
+                .lock()
+                .lines()
+                .next()
+                .unwrap()?
+                .trim()
+                .to_ascii_lowercase();
+
+            // The data portion is the same length for every key, so sample one
+            // to learn it without hard-coding the printable encoding's width.
+            let data_len = vanity_data_portion(&SecretKey::generate().public().to_string())
+                .chars()
+                .count();
+
+            if let Err(e) = validate_vanity_pattern(&prefix, &suffix, data_len) {
+                println!("{e}");
+                return Ok(false);
+            }
+
+            let num_threads = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4);
+            println!(
+                "Searching for a match with {} worker threads (expected ~{} attempts)...",
+                num_threads,
+                expected_vanity_attempts(&prefix, &suffix)
+            );
+
+            let found = Arc::new(AtomicBool::new(false));
+            let attempts = Arc::new(AtomicU64::new(0));
+            let winner: Arc<Mutex<Option<ZeroizingSecretKey>>> = Arc::new(Mutex::new(None));
+
+            std::thread::scope(|scope| {
+                for _ in 0..num_threads {
+                    let found = Arc::clone(&found);
+                    let attempts = Arc::clone(&attempts);
+                    let winner = Arc::clone(&winner);
+                    let prefix = prefix.as_str();
+                    let suffix = suffix.as_str();
+                    scope.spawn(move || {
+                        while !found.load(Ordering::Relaxed) {
+                            // Wrap every candidate immediately, so a losing
+                            // candidate is zeroized the moment it is dropped
+                            // rather than lingering as a bare `SecretKey`.
+                            let candidate = ZeroizingSecretKey::new(SecretKey::generate());
+                            let public_key = candidate.public();
+                            let printable = public_key.to_string();
+                            let data = vanity_data_portion(&printable);
+                            let count = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                            if count % 100_000 == 0 {
+                                println!("... {count} attempts so far");
+                            }
+                            if data.starts_with(prefix) && data.ends_with(suffix) {
+                                *winner.lock().unwrap() = Some(candidate);
+                                found.store(true, Ordering::Relaxed);
+                                break;
+                            }
+                        }
+                    });
+                }
+            });
+
+            let secret_key = winner.lock().unwrap().take().unwrap();
+            let public_key = secret_key.public();
+            println!(
+                "Found a match after {} attempts.",
+                attempts.load(Ordering::Relaxed)
+            );
+
             let password =
                 rpassword::prompt_password("Enter new password to encrypt your master key: ")?;
             println!("Encrypting...");
@@ -228,12 +548,33 @@ pub fn execute(option: MenuOption, params: &mut Params) -> Result<bool, Box<dyn
             println!("Master Key generated.");
             println!("Your Mosaic Identity is: {}", public_key);
         }
+        MenuOption::RestoreFromRecoveryPhrase => {
+            print!("Enter your recovery phrase: ");
+            io::stdout().flush()?;
+            let stdin = io::stdin();
+            let phrase = stdin.lock().lines().next().unwrap()?;
+
+            let mnemonic = bip39::Mnemonic::parse_normalized(phrase.trim())
+                .map_err(|e| format!("Invalid recovery phrase: {e}"))?;
+            let secret_key = SecretKey::from_bytes(&mnemonic.to_entropy())?;
+            let public_key = secret_key.public();
+
+            let password =
+                rpassword::prompt_password("Enter new password to encrypt your master key: ")?;
+            println!("Encrypting...");
+            let encrypted_secret_key =
+                EncryptedSecretKey::from_secret_key(&secret_key, &password, 18);
+            params.data.encrypted_master_key = Some(encrypted_secret_key);
+            params.secret_key = Some(ZeroizingSecretKey::new(secret_key));
+            println!("Master Key restored.");
+            println!("Your Mosaic Identity is: {}", public_key);
+        }
         MenuOption::DecryptMaster => match &params.data.encrypted_master_key {
             Some(e) => {
                 let password =
                     rpassword::prompt_password("Enter password to decrypt your master key: ")?;
                 println!("Decrypting...");
-                params.secret_key = Some(e.to_secret_key(&password)?);
+                params.secret_key = Some(ZeroizingSecretKey::new(e.to_secret_key(&password)?));
             }
             None => panic!("Menu option should not have been there!"),
         },
@@ -251,6 +592,19 @@ pub fn execute(option: MenuOption, params: &mut Params) -> Result<bool, Box<dyn
                 println!("Failed to confirm the operation. Taking no action.");
             }
         }
+        MenuOption::ShowRecoveryPhrase => {
+            let secret_key = params
+                .secret_key
+                .as_ref()
+                .ok_or("Master key is not decrypted")?;
+            let mnemonic = secret_key_to_mnemonic(secret_key)?;
+            println!("Your recovery phrase is:");
+            println!();
+            println!("    {mnemonic}");
+            println!();
+            println!("Write this down and store it somewhere safe and offline.");
+            println!("Anyone who has it can reconstruct your master key without your password.");
+        }
         MenuOption::NewBootstrap => {
             params.data.bootstrap = Some(UserBootstrap::new());
         }
@@ -295,9 +649,62 @@ pub fn execute(option: MenuOption, params: &mut Params) -> Result<bool, Box<dyn
         MenuOption::EditKeySchedule => {
             println!("Not implemented");
         }
+        MenuOption::RememberPassword => {
+            let emk = params
+                .data
+                .encrypted_master_key
+                .as_ref()
+                .ok_or("No master key to remember a password for")?;
+            let password = rpassword::prompt_password(
+                "Enter your master key password to remember it in the OS keyring: ",
+            )?;
+            // Verify the password actually decrypts before storing it.
+            let secret_key = emk.to_secret_key(&password)?;
+            keyring_store::remember_password(&secret_key.public(), &password)?;
+            println!("Password stored in OS keyring.");
+        }
+        MenuOption::ForgetPassword => {
+            let emk = params
+                .data
+                .encrypted_master_key
+                .as_ref()
+                .ok_or("No master key to forget a password for")?;
+            keyring_store::forget_password(&emk.public_key())?;
+            println!("Stored password, if any, removed from OS keyring.");
+        }
         MenuOption::SaveAndExit => {
-            let contents: String = serde_json::to_string(&params.data)?;
-            fs::write(&params.config_file, contents)?;
+            match &params.data.encrypted_master_key {
+                Some(emk) => atomic::atomic_write(&params.paths.master_key, &emk.to_bytes())?,
+                None if params.paths.master_key.exists() => {
+                    fs::remove_file(&params.paths.master_key)?
+                }
+                None => {}
+            }
+
+            match &params.data.bootstrap {
+                Some(b) => atomic::atomic_write(&params.paths.bootstrap, &b.to_bytes())?,
+                None if params.paths.bootstrap.exists() => {
+                    fs::remove_file(&params.paths.bootstrap)?
+                }
+                None => {}
+            }
+
+            match &params.data.profile {
+                Some(p) => atomic::atomic_write(&params.paths.profile, &p.to_bytes()?)?,
+                None if params.paths.profile.exists() => fs::remove_file(&params.paths.profile)?,
+                None => {}
+            }
+
+            match &params.data.key_schedule {
+                Some(ks) => {
+                    atomic::atomic_write(&params.paths.key_schedule, &encode_key_schedule(ks)?)?
+                }
+                None if params.paths.key_schedule.exists() => {
+                    fs::remove_file(&params.paths.key_schedule)?
+                }
+                None => {}
+            }
+
             println!("Saved.");
             return Ok(true);
         }
@@ -308,3 +715,104 @@ pub fn execute(option: MenuOption, params: &mut Params) -> Result<bool, Box<dyn
 
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vanity_data_portion_strips_the_hrp() {
+        assert_eq!(
+            vanity_data_portion("mopub1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq"),
+            "qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq"
+        );
+    }
+
+    #[test]
+    fn vanity_data_portion_passes_through_unrecognized_strings() {
+        // Defensive fallback: if the printable form ever doesn't carry the
+        // expected HRP, match against the whole string rather than panicking.
+        assert_eq!(vanity_data_portion("somethingelse"), "somethingelse");
+    }
+
+    #[test]
+    fn validate_vanity_pattern_accepts_alphabet_characters() {
+        assert!(validate_vanity_pattern("qpz", "7l", 58).is_ok());
+        assert!(validate_vanity_pattern("", "", 58).is_ok());
+    }
+
+    #[test]
+    fn validate_vanity_pattern_rejects_out_of_alphabet_characters() {
+        // '1' is bech32's reserved separator, not a data character.
+        assert!(validate_vanity_pattern("1", "", 58).is_err());
+        assert!(validate_vanity_pattern("", "b", 58).is_err());
+    }
+
+    #[test]
+    fn validate_vanity_pattern_rejects_patterns_longer_than_the_data_portion() {
+        assert!(validate_vanity_pattern("qpz", "7l", 4).is_err());
+        assert!(validate_vanity_pattern("qpz", "7l", 5).is_ok());
+    }
+
+    #[test]
+    fn expected_vanity_attempts_scales_with_pattern_length() {
+        assert_eq!(expected_vanity_attempts("", ""), 1);
+        assert_eq!(
+            expected_vanity_attempts("q", ""),
+            PRINTABLE_ALPHABET.len() as u64
+        );
+        assert_eq!(
+            expected_vanity_attempts("q", "p"),
+            (PRINTABLE_ALPHABET.len() as u64).pow(2)
+        );
+    }
+
+    #[test]
+    fn key_schedule_round_trips_when_empty() {
+        let encoded = encode_key_schedule(&[]).unwrap();
+        let decoded = decode_key_schedule(&encoded).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn key_schedule_encode_errors_without_a_real_encoding() {
+        // KeyCertificate has no binary encoding yet; encoding a non-empty
+        // schedule must fail cleanly rather than silently writing garbage.
+        assert!(encode_key_schedule(&[KeyCertificate]).is_err());
+    }
+
+    #[test]
+    fn key_schedule_decode_rejects_truncated_count() {
+        assert!(decode_key_schedule(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn key_schedule_decode_rejects_truncated_record_length() {
+        // Claims one record but cuts off before its length field.
+        let mut bytes = 1u32.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0, 0]);
+        assert!(decode_key_schedule(&bytes).is_err());
+    }
+
+    #[test]
+    fn key_schedule_decode_rejects_record_shorter_than_declared() {
+        // Claims one record of 100 bytes but provides none.
+        let mut bytes = 1u32.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&100u32.to_le_bytes());
+        assert!(decode_key_schedule(&bytes).is_err());
+    }
+
+    #[test]
+    fn secret_key_round_trips_through_its_recovery_phrase() {
+        let secret_key = SecretKey::generate();
+        let mnemonic = secret_key_to_mnemonic(&secret_key).unwrap();
+        let restored = SecretKey::from_bytes(&mnemonic.to_entropy()).unwrap();
+        assert_eq!(secret_key.to_bytes(), restored.to_bytes());
+    }
+
+    #[test]
+    fn restoring_from_a_garbage_phrase_is_rejected() {
+        let bogus = "not a valid bip39 phrase at all here, sorry";
+        assert!(bip39::Mnemonic::parse_normalized(bogus).is_err());
+    }
+}
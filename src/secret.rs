@@ -0,0 +1,30 @@
+use std::ops::Deref;
+
+use mosaic_core::SecretKey;
+use zeroize::Zeroize;
+
+// Wraps a decrypted `SecretKey` so that, however control leaves `main` --
+// normal exit, a Ctrl-C abort, or a panic unwind -- the key bytes held in
+// `Params` are overwritten before the memory is freed, rather than lingering
+// in process memory (or a swapped page) indefinitely.
+pub struct ZeroizingSecretKey(SecretKey);
+
+impl ZeroizingSecretKey {
+    pub fn new(secret_key: SecretKey) -> ZeroizingSecretKey {
+        ZeroizingSecretKey(secret_key)
+    }
+}
+
+impl Deref for ZeroizingSecretKey {
+    type Target = SecretKey;
+
+    fn deref(&self) -> &SecretKey {
+        &self.0
+    }
+}
+
+impl Drop for ZeroizingSecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}